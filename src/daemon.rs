@@ -0,0 +1,407 @@
+//! Event-driven daemon mode (`--daemon`).
+//!
+//! Rather than spawning `hyprctl clients -j` / `hyprctl activewindow -j` on
+//! every invocation, the daemon subscribes to Hyprland's event socket and
+//! keeps an in-memory [`Client`] list up to date as windows open, close, and
+//! change title/focus. It serves raise-or-launch requests over a small
+//! local Unix command socket, so a keybind can hit the live cache instead
+//! of re-scanning the window list each time.
+
+use crate::{Args, Client, ClientSource, HyprctlSource};
+use anyhow::{bail, Context, Result};
+use argh::FromArgs;
+use miniserde::json;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn command_socket_path() -> Result<PathBuf> {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").context("XDG_RUNTIME_DIR is not set")?;
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+        .context("HYPRLAND_INSTANCE_SIGNATURE is not set (not running under Hyprland?)")?;
+    Ok(PathBuf::from(runtime_dir).join(format!("raise-{signature}.sock")))
+}
+
+fn event_socket_path() -> Result<PathBuf> {
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").context("XDG_RUNTIME_DIR is not set")?;
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+        .context("HYPRLAND_INSTANCE_SIGNATURE is not set (not running under Hyprland?)")?;
+    Ok(PathBuf::from(runtime_dir)
+        .join("hypr")
+        .join(signature)
+        .join(".socket2.sock"))
+}
+
+/// The live cache fed by [`listen_events`]. Implements [`ClientSource`] so
+/// `crate::run` can't tell it apart from [`HyprctlSource`].
+struct LiveClientCache {
+    clients: Arc<Mutex<Vec<Client>>>,
+    focused: Arc<Mutex<Option<String>>>,
+}
+
+impl LiveClientCache {
+    fn new() -> Result<Self> {
+        let clients = Arc::new(Mutex::new(HyprctlSource.clients().unwrap_or_default()));
+        let focused = Arc::new(Mutex::new(
+            HyprctlSource
+                .current_window()
+                .ok()
+                .map(|client| client.address),
+        ));
+
+        let event_path = event_socket_path()?;
+        let thread_clients = Arc::clone(&clients);
+        let thread_focused = Arc::clone(&focused);
+        std::thread::spawn(move || {
+            if let Err(err) = listen_events(&event_path, &thread_clients, &thread_focused) {
+                eprintln!("raise daemon: event listener stopped: {err:#}");
+            }
+        });
+
+        Ok(Self { clients, focused })
+    }
+}
+
+impl ClientSource for LiveClientCache {
+    fn clients(&self) -> Result<Vec<Client>> {
+        Ok(self.clients.lock().unwrap().clone())
+    }
+
+    fn current_window(&self) -> Result<Client> {
+        let address = self
+            .focused
+            .lock()
+            .unwrap()
+            .clone()
+            .context("No window is currently focused")?;
+        self.clients
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|client| client.address == address)
+            .cloned()
+            .context("Focused window is not in the tracked client list")
+    }
+}
+
+/// Hyprland event addresses come bare (`55cb…`); `hyprctl -j` addresses are
+/// `0x`-prefixed. Normalize to the latter so the two sources agree.
+fn normalize_address(raw: &str) -> String {
+    if raw.starts_with("0x") {
+        raw.to_owned()
+    } else {
+        format!("0x{raw}")
+    }
+}
+
+/// Applies one `event>>payload` line from the Hyprland event socket to the
+/// cache. Windows opened after the daemon started only carry what the
+/// `openwindow` event reports (class and title); unlike `hyprctl clients -j`
+/// there's no `tag`/`xdgTag` in the event stream, so those stay `None` for
+/// such windows until the daemon is restarted.
+fn apply_event(line: &str, clients: &Mutex<Vec<Client>>, focused: &Mutex<Option<String>>) {
+    let Some((event, payload)) = line.split_once(">>") else {
+        return;
+    };
+
+    match event {
+        "openwindow" => {
+            let mut fields = payload.splitn(4, ',');
+            let (Some(address), Some(_workspace), Some(class), Some(title)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return;
+            };
+            clients.lock().unwrap().push(Client {
+                class: class.to_owned(),
+                address: normalize_address(address),
+                initial_class: Some(class.to_owned()),
+                title: Some(title.to_owned()),
+                initial_title: Some(title.to_owned()),
+                tag: None,
+                xdg_tag: None,
+            });
+        }
+        "closewindow" => {
+            let address = normalize_address(payload);
+            clients
+                .lock()
+                .unwrap()
+                .retain(|client| client.address != address);
+        }
+        "windowtitlev2" => {
+            let Some((address, title)) = payload.split_once(',') else {
+                return;
+            };
+            let address = normalize_address(address);
+            if let Some(client) = clients
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .find(|client| client.address == address)
+            {
+                client.title = Some(title.to_owned());
+            }
+        }
+        "activewindowv2" => {
+            let address = (!payload.is_empty()).then(|| normalize_address(payload));
+            *focused.lock().unwrap() = address;
+        }
+        _ => {}
+    }
+}
+
+fn listen_events(
+    path: &std::path::Path,
+    clients: &Arc<Mutex<Vec<Client>>>,
+    focused: &Arc<Mutex<Option<String>>>,
+) -> Result<()> {
+    let stream = UnixStream::connect(path)
+        .with_context(|| format!("Connecting to Hyprland event socket `{}`", path.display()))?;
+    for line in BufReader::new(stream).lines() {
+        let line = line.context("Reading from Hyprland event socket")?;
+        apply_event(&line, clients, focused);
+    }
+    Ok(())
+}
+
+/// Services one client connection: parses the forwarded argv, runs it
+/// against the live cache, and writes a status line back (`ok`, or
+/// `error: <message>`) so the client can surface failures instead of
+/// firing-and-forgetting. Returns the same result so the accept loop in
+/// [`run`] can still log it server-side.
+fn handle_request(stream: UnixStream, cache: &LiveClientCache) -> Result<()> {
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .context("Reading request from client")?;
+
+    let result = (|| -> Result<()> {
+        let argv: Vec<String> =
+            json::from_str(line.trim_end()).context("Parsing request as a JSON argv array")?;
+        let argv_refs: Vec<&str> = argv.iter().map(String::as_str).collect();
+        let args = Args::from_args(&["raise"], &argv_refs)
+            .map_err(|early_exit| anyhow::anyhow!(early_exit.output))
+            .context("Parsing forwarded command-line arguments")?;
+
+        crate::run(&args, cache)
+    })();
+
+    let status = match &result {
+        Ok(()) => "ok".to_owned(),
+        Err(err) => format!("error: {err:#}"),
+    };
+    writeln!(&stream, "{status}").context("Writing status to client")?;
+
+    result
+}
+
+/// Runs the daemon: seeds and maintains the live client cache, then serves
+/// raise-or-launch requests on the command socket forever.
+pub(crate) fn run() -> Result<()> {
+    let cache = LiveClientCache::new()?;
+
+    let socket_path = command_socket_path()?;
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(&socket_path)
+        .with_context(|| format!("Binding command socket `{}`", socket_path.display()))?;
+
+    for incoming in listener.incoming() {
+        let stream = match incoming {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("raise daemon: accept failed: {err}");
+                continue;
+            }
+        };
+        if let Err(err) = handle_request(stream, &cache) {
+            eprintln!("raise daemon: {err:#}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves a relative `--rules <path>`/`--rules=<path>` argument in `argv`
+/// to an absolute path. The daemon services requests from its own working
+/// directory, not the invoking client's, so forwarding a relative path
+/// as-is would resolve it against the wrong directory once handed off.
+fn absolutize_rules_path(argv: &[String]) -> Vec<String> {
+    let mut argv = argv.to_vec();
+    let mut i = 0;
+    while i < argv.len() {
+        if argv[i] == "--rules" {
+            if let Some(path) = argv.get(i + 1).and_then(|path| absolutize(path)) {
+                argv[i + 1] = path;
+            }
+        } else if let Some(path) = argv[i].strip_prefix("--rules=").and_then(absolutize) {
+            argv[i] = format!("--rules={path}");
+        }
+        i += 1;
+    }
+    argv
+}
+
+/// Joins `path` onto the current working directory if it's relative;
+/// returns `None` for an already-absolute path (nothing to do) or if the
+/// working directory can't be determined.
+fn absolutize(path: &str) -> Option<String> {
+    if std::path::Path::new(path).is_absolute() {
+        return None;
+    }
+    let cwd = std::env::current_dir().ok()?;
+    Some(cwd.join(path).to_string_lossy().into_owned())
+}
+
+/// Forwards `argv` to a running daemon's command socket, if one is up.
+/// Returns `Ok(true)` if the request was handed off and the daemon reported
+/// success, `Ok(false)` if there's no daemon to talk to (the caller should
+/// fall back to `HyprctlSource`). Once the request has actually been handed
+/// off, a failure reported by the daemon is returned as `Err` rather than
+/// `Ok(false)`: the daemon may already have acted on it, so silently falling
+/// back and re-running it via `HyprctlSource` could double it up.
+pub(crate) fn dispatch_to_daemon(argv: &[String]) -> Result<bool> {
+    let Ok(socket_path) = command_socket_path() else {
+        return Ok(false);
+    };
+    let Ok(mut stream) = UnixStream::connect(&socket_path) else {
+        return Ok(false);
+    };
+
+    let argv = absolutize_rules_path(argv);
+    writeln!(stream, "{}", json::to_string(&argv)).context("Writing request to raise daemon")?;
+    stream.flush().ok();
+
+    let mut status = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut status)
+        .context("Reading status from raise daemon")?;
+
+    let status = status.trim_end();
+    if status == "ok" {
+        return Ok(true);
+    }
+    match status.strip_prefix("error: ") {
+        Some(message) => bail!("raise daemon: {message}"),
+        None => bail!("raise daemon: unexpected response `{status}`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(clients: &'a [Client], address: &str) -> Option<&'a Client> {
+        clients.iter().find(|client| client.address == address)
+    }
+
+    #[test]
+    fn normalize_address_adds_missing_prefix() {
+        assert_eq!(normalize_address("55cb1234"), "0x55cb1234");
+        assert_eq!(normalize_address("0x55cb1234"), "0x55cb1234");
+    }
+
+    #[test]
+    fn openwindow_event_adds_a_client() {
+        let clients = Mutex::new(Vec::new());
+        let focused = Mutex::new(None);
+        apply_event(
+            "openwindow>>55cb1234,1,firefox,Mozilla Firefox",
+            &clients,
+            &focused,
+        );
+
+        let clients = clients.into_inner().unwrap();
+        let client = find(&clients, "0x55cb1234").expect("client was added");
+        assert_eq!(client.class, "firefox");
+        assert_eq!(client.title.as_deref(), Some("Mozilla Firefox"));
+    }
+
+    #[test]
+    fn closewindow_event_removes_a_client() {
+        let clients = Mutex::new(vec![Client {
+            class: "firefox".to_owned(),
+            address: "0x55cb1234".to_owned(),
+            initial_class: None,
+            title: None,
+            initial_title: None,
+            tag: None,
+            xdg_tag: None,
+        }]);
+        let focused = Mutex::new(None);
+        apply_event("closewindow>>55cb1234", &clients, &focused);
+
+        assert!(clients.into_inner().unwrap().is_empty());
+    }
+
+    #[test]
+    fn windowtitlev2_event_updates_title() {
+        let clients = Mutex::new(vec![Client {
+            class: "firefox".to_owned(),
+            address: "0x55cb1234".to_owned(),
+            initial_class: None,
+            title: Some("Old Title".to_owned()),
+            initial_title: None,
+            tag: None,
+            xdg_tag: None,
+        }]);
+        let focused = Mutex::new(None);
+        apply_event("windowtitlev2>>55cb1234,New Title", &clients, &focused);
+
+        let clients = clients.into_inner().unwrap();
+        assert_eq!(
+            find(&clients, "0x55cb1234").unwrap().title.as_deref(),
+            Some("New Title")
+        );
+    }
+
+    #[test]
+    fn activewindowv2_event_tracks_focus() {
+        let clients = Mutex::new(Vec::new());
+        let focused = Mutex::new(None);
+        apply_event("activewindowv2>>55cb1234", &clients, &focused);
+        assert_eq!(focused.lock().unwrap().as_deref(), Some("0x55cb1234"));
+
+        apply_event("activewindowv2>>", &clients, &focused);
+        assert_eq!(*focused.lock().unwrap(), None);
+    }
+
+    #[test]
+    fn absolutize_rules_path_resolves_relative_separate_arg() {
+        let cwd = std::env::current_dir().unwrap();
+        let argv = vec!["--rules".to_owned(), "rules.json".to_owned()];
+        assert_eq!(
+            absolutize_rules_path(&argv),
+            vec![
+                "--rules".to_owned(),
+                cwd.join("rules.json").to_string_lossy().into_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn absolutize_rules_path_resolves_relative_equals_form() {
+        let cwd = std::env::current_dir().unwrap();
+        let argv = vec!["--rules=rules.json".to_owned()];
+        assert_eq!(
+            absolutize_rules_path(&argv),
+            vec![format!(
+                "--rules={}",
+                cwd.join("rules.json").to_string_lossy()
+            )]
+        );
+    }
+
+    #[test]
+    fn absolutize_rules_path_leaves_absolute_path_alone() {
+        let argv = vec!["--rules".to_owned(), "/etc/raise/rules.json".to_owned()];
+        assert_eq!(absolutize_rules_path(&argv), argv);
+    }
+
+    #[test]
+    fn absolutize_rules_path_leaves_unrelated_args_alone() {
+        let argv = vec!["--class".to_owned(), "Firefox".to_owned()];
+        assert_eq!(absolutize_rules_path(&argv), argv);
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::{bail, Context, Result};
 use argh::FromArgs;
+use globset::{Glob, GlobMatcher};
 use miniserde::{json, Deserialize};
 use regex::Regex;
 use std::process::{Child, Command};
 
+mod daemon;
+
 #[derive(Debug, Clone)]
 struct MatchCondition {
     field: MatchField,
@@ -16,10 +19,7 @@ impl MatchCondition {
     }
 
     fn matches(&self, client: &Client) -> bool {
-        self.field
-            .value(client)
-            .map(|value| self.matcher.matches(value))
-            .unwrap_or(false)
+        self.matcher.matches(self.field.value(client))
     }
 }
 
@@ -65,6 +65,17 @@ enum Matcher {
     Prefix(String),
     Suffix(String),
     Regex(Regex),
+    Glob(GlobMatcher),
+    /// Case-folded equivalents of `Equals`/`Contains`/`Prefix`/`Suffix`. The
+    /// pattern is lower-cased once, up front; `matches` lower-cases the
+    /// value to compare, using Unicode-aware case folding so this works
+    /// beyond ASCII.
+    IEquals(String),
+    IContains(String),
+    IPrefix(String),
+    ISuffix(String),
+    /// Inverts an inner matcher, for the `field:method!=pattern` syntax.
+    Negate(Box<Matcher>),
 }
 
 impl Matcher {
@@ -78,21 +89,238 @@ impl Matcher {
             "regex" | "re" => Regex::new(pattern)
                 .map(Self::Regex)
                 .map_err(|err| format!("Invalid regex `{pattern}`: {err}")),
+            "glob" => Glob::new(pattern)
+                .map(|glob| Self::Glob(glob.compile_matcher()))
+                .map_err(|err| format!("Invalid glob `{pattern}`: {err}")),
+            "ieq" | "iequals" => Ok(Self::IEquals(pattern.to_lowercase())),
+            "icontains" | "isubstr" => Ok(Self::IContains(pattern.to_lowercase())),
+            "iprefix" | "istarts-with" | "istartswith" => Ok(Self::IPrefix(pattern.to_lowercase())),
+            "isuffix" | "iends-with" | "iendswith" => Ok(Self::ISuffix(pattern.to_lowercase())),
             _ => Err(format!("Unsupported match method `{method}`")),
         }
     }
 
-    fn matches(&self, value: &str) -> bool {
+    /// `value` is `None` when the client has no value for the matched
+    /// field (e.g. an unset `tag`). `Negate` must see that absence itself
+    /// rather than have its caller short-circuit to `false` first, or a
+    /// negated matcher like `tag:contains!=work` would wrongly treat a
+    /// missing tag as if "work" had matched.
+    fn matches(&self, value: Option<&str>) -> bool {
+        if let Self::Negate(inner) = self {
+            return !inner.matches(value);
+        }
+
+        let Some(value) = value else {
+            return false;
+        };
+
         match self {
             Self::Equals(pattern) => value == pattern,
             Self::Contains(pattern) => value.contains(pattern),
             Self::Prefix(pattern) => value.starts_with(pattern),
             Self::Suffix(pattern) => value.ends_with(pattern),
             Self::Regex(regex) => regex.is_match(value),
+            Self::Glob(glob) => glob.is_match(value),
+            Self::IEquals(pattern) => value.to_lowercase() == *pattern,
+            Self::IContains(pattern) => value.to_lowercase().contains(pattern.as_str()),
+            Self::IPrefix(pattern) => value.to_lowercase().starts_with(pattern.as_str()),
+            Self::ISuffix(pattern) => value.to_lowercase().ends_with(pattern.as_str()),
+            Self::Negate(_) => unreachable!("handled above"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum MatchExpr {
+    Cond(MatchCondition),
+    All(Vec<MatchExpr>),
+    Any(Vec<MatchExpr>),
+    Not(Box<MatchExpr>),
+}
+
+impl MatchExpr {
+    fn matches(&self, client: &Client) -> bool {
+        match self {
+            Self::Cond(cond) => cond.matches(client),
+            Self::All(exprs) => exprs.iter().all(|expr| expr.matches(client)),
+            Self::Any(exprs) => exprs.iter().any(|expr| expr.matches(client)),
+            Self::Not(expr) => !expr.matches(client),
         }
     }
 }
 
+/// Recursive-descent parser for the `--expr` boolean matcher language.
+///
+/// Grammar: `expr := ident '(' (expr (',' expr)*)? ')' | leaf`, where `ident`
+/// is one of `all`/`any`/`not` and `leaf` is a `field[:method]=pattern` token
+/// reused from [`parse_match_condition`]. Commas only split arguments at the
+/// depth they appear at, so nested groups don't get split early.
+struct ExprParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> ExprParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn parse(mut self) -> std::result::Result<MatchExpr, String> {
+        let expr = self.parse_expr(false)?;
+        self.skip_ws();
+        if self.pos != self.input.len() {
+            return Err(format!(
+                "Unexpected trailing input `{}`",
+                &self.input[self.pos..]
+            ));
+        }
+        Ok(expr)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(ch) = self.rest().chars().next() {
+            if !ch.is_whitespace() {
+                break;
+            }
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn peek_ident(&self) -> Option<&'static str> {
+        let rest = self.rest();
+        for name in ["all", "any", "not"] {
+            if let Some(after) = rest.strip_prefix(name) {
+                if after.trim_start().starts_with('(') {
+                    return Some(name);
+                }
+            }
+        }
+        None
+    }
+
+    /// `in_group` is true while parsing an argument of an enclosing
+    /// `name(...)` list, where a top-level `,` or `)` ends the expression.
+    /// A standalone leaf at the top of the input has no such boundary, so
+    /// literal commas in its pattern (e.g. a window title) aren't split.
+    fn parse_expr(&mut self, in_group: bool) -> std::result::Result<MatchExpr, String> {
+        self.skip_ws();
+        if let Some(name) = self.peek_ident() {
+            self.pos += name.len();
+            self.skip_ws();
+            if !self.rest().starts_with('(') {
+                return Err(format!("Expected `(` after `{name}`"));
+            }
+            self.pos += 1;
+            let children = self.parse_expr_list()?;
+            self.skip_ws();
+            if !self.rest().starts_with(')') {
+                return Err(format!("Expected `)` to close `{name}(`"));
+            }
+            self.pos += 1;
+            return match name {
+                "all" => Ok(MatchExpr::All(children)),
+                "any" => Ok(MatchExpr::Any(children)),
+                "not" => {
+                    let mut children = children.into_iter();
+                    let (Some(only), None) = (children.next(), children.next()) else {
+                        return Err("`not(...)` expects exactly one expression".to_string());
+                    };
+                    Ok(MatchExpr::Not(Box::new(only)))
+                }
+                _ => unreachable!("peek_ident only returns known identifiers"),
+            };
+        }
+
+        let leaf = self.parse_leaf_token(in_group);
+        parse_match_condition(leaf.trim()).map(MatchExpr::Cond)
+    }
+
+    fn parse_expr_list(&mut self) -> std::result::Result<Vec<MatchExpr>, String> {
+        let mut exprs = Vec::new();
+        self.skip_ws();
+        if self.rest().starts_with(')') {
+            return Ok(exprs);
+        }
+        loop {
+            exprs.push(self.parse_expr(true)?);
+            self.skip_ws();
+            if self.rest().starts_with(',') {
+                self.pos += 1;
+                continue;
+            }
+            break;
+        }
+        Ok(exprs)
+    }
+
+    /// Reads a leaf token. Inside a `name(...)` argument list (`in_group`),
+    /// stops at the next `,` or `)` outside quotes; at the top level,
+    /// there's no such boundary, so it reads to the end of the input.
+    ///
+    /// A leaf's pattern can itself contain `(`, `)`, or `,` (window titles
+    /// commonly do), and a leaf never contains a nested `name(...)` group
+    /// itself (those are recognized by `parse_expr` before it ever calls
+    /// here), so `(`/`)` in a leaf's text can't be structural — only
+    /// quoting, not paren-depth counting, can tell a literal `)` apart from
+    /// one that closes the enclosing group. Wrap the pattern in `"..."`
+    /// (with `\"`/`\\` escapes) to include a literal `,`/`)` in a grouped
+    /// leaf, e.g. `any(title="Step 3) Done", class=Foo)`.
+    fn parse_leaf_token(&mut self, in_group: bool) -> &'a str {
+        let start = self.pos;
+        let bytes = self.input.as_bytes();
+        let mut in_quotes = false;
+        while self.pos < bytes.len() {
+            match bytes[self.pos] {
+                b'\\' if in_quotes && self.pos + 1 < bytes.len() => {
+                    self.pos += 1;
+                }
+                b'"' => in_quotes = !in_quotes,
+                b')' if !in_quotes && in_group => break,
+                b',' if !in_quotes && in_group => break,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+}
+
+fn parse_match_expr(value: &str) -> std::result::Result<MatchExpr, String> {
+    ExprParser::new(value).parse()
+}
+
+/// If `pattern` is wrapped in double quotes, unescapes it (`\"` and `\\`)
+/// and returns the quoted content; otherwise returns it unchanged. Quoting
+/// lets a leaf's pattern use characters (`,`, `)`) that would otherwise be
+/// mistaken for `--expr` group delimiters — see `ExprParser::parse_leaf_token`.
+fn unquote_pattern(pattern: &str) -> std::result::Result<std::borrow::Cow<'_, str>, String> {
+    let Some(inner) = pattern
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return Ok(std::borrow::Cow::Borrowed(pattern));
+    };
+
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some(escaped @ ('"' | '\\')) => unescaped.push(escaped),
+            Some(other) => return Err(format!("Invalid escape `\\{other}` in quoted pattern")),
+            None => return Err("Trailing `\\` in quoted pattern".to_string()),
+        }
+    }
+    Ok(std::borrow::Cow::Owned(unescaped))
+}
+
 fn parse_match_condition(value: &str) -> std::result::Result<MatchCondition, String> {
     let (selector, pattern) = value
         .split_once('=')
@@ -102,6 +330,18 @@ fn parse_match_condition(value: &str) -> std::result::Result<MatchCondition, Str
         return Err("Matcher pattern cannot be empty".to_string());
     }
 
+    let pattern = unquote_pattern(pattern)?;
+    if pattern.is_empty() {
+        return Err("Matcher pattern cannot be empty".to_string());
+    }
+
+    // A trailing `!` on the selector (e.g. `title:contains!=Private`,
+    // `class!=firefox`) negates the matcher.
+    let (selector, negate) = match selector.strip_suffix('!') {
+        Some(stripped) => (stripped, true),
+        None => (selector, false),
+    };
+
     let (field_token, method_token) = match selector.split_once(':') {
         Some((field, method)) => (field, Some(method)),
         None => (selector, None),
@@ -110,21 +350,26 @@ fn parse_match_condition(value: &str) -> std::result::Result<MatchCondition, Str
     let field = MatchField::parse(field_token)
         .ok_or_else(|| format!("Unsupported match field `{field_token}`"))?;
 
-    let matcher = Matcher::from_tokens(method_token, pattern)?;
+    let matcher = Matcher::from_tokens(method_token, &pattern)?;
+    let matcher = if negate {
+        Matcher::Negate(Box::new(matcher))
+    } else {
+        matcher
+    };
 
     Ok(MatchCondition::new(field, matcher))
 }
 
 #[derive(FromArgs)]
 /// Raise window if it exists, otherwise launch new window.
-struct Args {
+pub(crate) struct Args {
     /// class to focus (shorthand for `--match class=...`)
     #[argh(option, short = 'c')]
     class: Option<String>,
 
-    /// command to launch
+    /// command to launch when no matching window is found (unused in `--rules` mode)
     #[argh(option, short = 'e')]
-    launch: String,
+    launch: Option<String>,
 
     /// additional matchers in the form field[:method]=pattern
     #[argh(
@@ -134,10 +379,35 @@ struct Args {
         from_str_fn(parse_match_condition)
     )]
     matches: Vec<MatchCondition>,
+
+    /// boolean matcher expression, e.g. `any(class=Firefox, class=Chromium)`;
+    /// overrides `--class`/`--match` when given
+    #[argh(option, long = "expr", from_str_fn(parse_match_expr))]
+    expr: Option<MatchExpr>,
+
+    /// path to a rules config file (JSON) of match+consequence rules; when
+    /// given, runs in rule-engine mode instead of raise-or-launch
+    #[argh(option, long = "rules")]
+    rules: Option<String>,
+
+    /// apply each rule's consequences to every matching window instead of
+    /// only the first (only meaningful with `--rules`)
+    #[argh(switch, long = "all")]
+    all: bool,
+
+    /// run as a long-lived daemon that tracks windows over the Hyprland
+    /// event socket and serves raise-or-launch requests over a local
+    /// command socket, instead of shelling out to `hyprctl` per invocation
+    #[argh(switch, long = "daemon")]
+    daemon: bool,
 }
 
 impl Args {
-    fn build_matchers(&self) -> Result<Vec<MatchCondition>> {
+    fn build_expr(&self) -> Result<MatchExpr> {
+        if let Some(expr) = &self.expr {
+            return Ok(expr.clone());
+        }
+
         let mut matchers = Vec::new();
 
         if let Some(class) = &self.class {
@@ -150,33 +420,259 @@ impl Args {
         matchers.extend(self.matches.clone());
 
         if matchers.is_empty() {
-            bail!("Provide at least one matcher via --class or --match");
+            bail!("Provide at least one matcher via --class, --match, or --expr");
         }
 
-        Ok(matchers)
+        Ok(MatchExpr::All(
+            matchers.into_iter().map(MatchExpr::Cond).collect(),
+        ))
     }
 }
 
-#[derive(Deserialize, Debug)]
-struct Client {
-    class: String,
-    address: String,
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct Client {
+    pub(crate) class: String,
+    pub(crate) address: String,
     #[serde(rename = "initialClass")]
-    initial_class: Option<String>,
-    title: Option<String>,
+    pub(crate) initial_class: Option<String>,
+    pub(crate) title: Option<String>,
     #[serde(rename = "initialTitle")]
-    initial_title: Option<String>,
-    tag: Option<String>,
+    pub(crate) initial_title: Option<String>,
+    pub(crate) tag: Option<String>,
     #[serde(rename = "xdgTag")]
-    xdg_tag: Option<String>,
+    pub(crate) xdg_tag: Option<String>,
+}
+
+/// Abstracts how the current set of windows and the focused window are
+/// learned: either by shelling out to `hyprctl` per call, or from a live
+/// cache fed by the `--daemon` event-socket listener (see `daemon`).
+pub(crate) trait ClientSource {
+    fn clients(&self) -> Result<Vec<Client>>;
+    fn current_window(&self) -> Result<Client>;
+}
+
+pub(crate) struct HyprctlSource;
+
+impl ClientSource for HyprctlSource {
+    fn clients(&self) -> Result<Vec<Client>> {
+        let output = Command::new("hyprctl")
+            .arg("clients")
+            .arg("-j")
+            .output()
+            .context("Running `hyprctl clients -j` failed")?;
+        if !output.status.success() {
+            bail!("`hyprctl clients -j` exited unsuccessfully");
+        }
+        let stdout = String::from_utf8(output.stdout)
+            .context("Reading `hyprctl clients -j` to string failed")?;
+        json::from_str(&stdout).context("Failed to parse `hyprctl clients -j`")
+    }
+
+    fn current_window(&self) -> Result<Client> {
+        let output = Command::new("hyprctl")
+            .arg("activewindow")
+            .arg("-j")
+            .output()
+            .context("Running `hyprctl activewindow -j` failed")?;
+        let stdout = String::from_utf8(output.stdout)
+            .context("Reading `hyprctl activewindow -j` to string failed")?;
+        json::from_str(&stdout).context("Failed to parse `hyprctl activewindow -j`")
+    }
+}
+
+/// A `hyprctl dispatch` verb to run against a matched window, beyond the
+/// built-in focus-or-launch behavior.
+#[derive(Debug, Clone)]
+enum Consequence {
+    MoveToWorkspace(String),
+    ToggleFloating,
+    ResizeWindowPixel { width: String, height: String },
+    Pin,
+    Fullscreen,
+    Exec(String),
+}
+
+impl Consequence {
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::MoveToWorkspace(_) => "movetoworkspace",
+            Self::ToggleFloating => "togglefloating",
+            Self::ResizeWindowPixel { .. } => "resizewindowpixel",
+            Self::Pin => "pin",
+            Self::Fullscreen => "fullscreen",
+            Self::Exec(_) => "exec",
+        }
+    }
+
+    /// Builds the `hyprctl dispatch <verb> <args>` argument list, targeting
+    /// `address` as the window to act on. `Pin` and `Fullscreen` take no
+    /// window selector: unlike `movetoworkspace`/`togglefloating`/
+    /// `resizewindowpixel`, Hyprland's `pin` and `fullscreen` dispatchers
+    /// only ever act on the focused window, so `dispatch_consequence`
+    /// focuses `address` first. `Exec` ignores `address` entirely: it has
+    /// no window to target, it launches a new one.
+    fn to_dispatch_args(&self, address: &str) -> Vec<String> {
+        let window = format!("address:{address}");
+        match self {
+            Self::MoveToWorkspace(workspace) => {
+                vec![self.verb().to_owned(), format!("{workspace},{window}")]
+            }
+            Self::ToggleFloating => vec![self.verb().to_owned(), window],
+            Self::ResizeWindowPixel { width, height } => vec![
+                self.verb().to_owned(),
+                format!("exact {width} {height},{window}"),
+            ],
+            Self::Pin | Self::Fullscreen => vec![self.verb().to_owned()],
+            Self::Exec(command) => vec![self.verb().to_owned(), command.clone()],
+        }
+    }
+}
+
+fn parse_consequence(value: &str) -> std::result::Result<Consequence, String> {
+    let (verb, arg) = match value.split_once(':') {
+        Some((verb, arg)) => (verb, Some(arg)),
+        None => (value, None),
+    };
+
+    match verb {
+        "movetoworkspace" => {
+            let workspace = arg.ok_or_else(|| {
+                "`movetoworkspace` requires a workspace, e.g. `movetoworkspace:3`".to_string()
+            })?;
+            Ok(Consequence::MoveToWorkspace(workspace.to_owned()))
+        }
+        "togglefloating" => Ok(Consequence::ToggleFloating),
+        "resizewindowpixel" => {
+            let arg = arg.ok_or_else(|| {
+                "`resizewindowpixel` requires `width,height`, e.g. `resizewindowpixel:640,480`"
+                    .to_string()
+            })?;
+            let (width, height) = arg.split_once(',').ok_or_else(|| {
+                format!("Expected `width,height` for `resizewindowpixel`, got `{arg}`")
+            })?;
+            Ok(Consequence::ResizeWindowPixel {
+                width: width.to_owned(),
+                height: height.to_owned(),
+            })
+        }
+        "pin" => Ok(Consequence::Pin),
+        "fullscreen" => Ok(Consequence::Fullscreen),
+        "exec" => {
+            let command =
+                arg.ok_or_else(|| "`exec` requires a command, e.g. `exec:firefox`".to_string())?;
+            Ok(Consequence::Exec(command.to_owned()))
+        }
+        _ => Err(format!("Unsupported consequence `{verb}`")),
+    }
 }
 
-fn launch_command(args: &Args) -> std::io::Result<Child> {
+/// One entry in a `--rules` config file: a match expression paired with the
+/// consequences to dispatch against the first matching window (or every
+/// matching window, with `--all`).
+#[derive(Debug, Clone)]
+struct Rule {
+    expr: MatchExpr,
+    consequences: Vec<Consequence>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawRule {
+    #[serde(rename = "match")]
+    r#match: String,
+    consequences: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawRulesConfig {
+    rules: Vec<RawRule>,
+}
+
+fn load_rules(path: &str) -> Result<Vec<Rule>> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("Reading rules file `{path}`"))?;
+    let raw: RawRulesConfig = json::from_str(&contents)
+        .with_context(|| format!("Parsing rules file `{path}` as JSON"))?;
+
+    raw.rules
+        .into_iter()
+        .map(|raw_rule| {
+            let expr = parse_match_expr(&raw_rule.r#match)
+                .map_err(|err| anyhow::anyhow!(err))
+                .with_context(|| format!("Invalid `match` expression `{}`", raw_rule.r#match))?;
+            let consequences = raw_rule
+                .consequences
+                .iter()
+                .map(|value| {
+                    parse_consequence(value)
+                        .map_err(|err| anyhow::anyhow!(err))
+                        .with_context(|| format!("Invalid consequence `{value}`"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Rule { expr, consequences })
+        })
+        .collect()
+}
+
+fn dispatch_consequence(address: &str, consequence: &Consequence) -> Result<Child> {
+    // `pin`/`fullscreen` only act on the focused window (see
+    // `Consequence::to_dispatch_args`), so focus the matched window first,
+    // waiting for that dispatch to finish before issuing the next one —
+    // otherwise the two race to reach the compositor and pin/fullscreen can
+    // land before the focus change takes effect, applying to whatever
+    // window was previously focused instead of the matched one.
+    if matches!(consequence, Consequence::Pin | Consequence::Fullscreen) {
+        focus_window(address)
+            .context("Focusing window before pin/fullscreen")?
+            .wait()
+            .context("Waiting for focus dispatch before pin/fullscreen")?;
+    }
+
+    Command::new("hyprctl")
+        .arg("dispatch")
+        .args(consequence.to_dispatch_args(address))
+        .spawn()
+        .with_context(|| format!("Dispatching `{}` for {address}", consequence.verb()))
+}
+
+/// Applies every rule's consequences, in rule order, to the first matching
+/// window (or to every matching window, if `apply_to_all` is set).
+/// `dispatch` is a parameter (rather than calling `dispatch_consequence`
+/// directly) so this can be tested without shelling out to `hyprctl`.
+fn apply_rules(
+    clients: &[Client],
+    rules: &[Rule],
+    apply_to_all: bool,
+    mut dispatch: impl FnMut(&str, &Consequence) -> Result<()>,
+) -> Result<()> {
+    for rule in rules {
+        let mut matching = clients.iter().filter(|client| rule.expr.matches(client));
+        if apply_to_all {
+            for client in matching {
+                for consequence in &rule.consequences {
+                    dispatch(&client.address, consequence)?;
+                }
+            }
+        } else if let Some(client) = matching.next() {
+            for consequence in &rule.consequences {
+                dispatch(&client.address, consequence)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn launch_command(args: &Args) -> Result<Child> {
+    let launch = args
+        .launch
+        .as_deref()
+        .context("Provide a command to launch via --launch")?;
     Command::new("hyprctl")
         .arg("keyword")
         .arg("exec")
-        .arg(&args.launch)
+        .arg(launch)
         .spawn()
+        .context("Spawning `hyprctl keyword exec` failed")
 }
 
 fn focus_window(address: &str) -> std::io::Result<Child> {
@@ -187,51 +683,51 @@ fn focus_window(address: &str) -> std::io::Result<Child> {
         .spawn()
 }
 
-fn get_current_matching_window(matchers: &[MatchCondition]) -> Result<Client> {
-    let output = Command::new("hyprctl")
-        .arg("activewindow")
-        .arg("-j")
-        .output()?;
-    let stdout = String::from_utf8(output.stdout)
-        .context("Reading `hyprctl currentwindow -j` to string failed")?;
-    let client = json::from_str::<Client>(&stdout)?;
-    if matchers.iter().all(|matcher| matcher.matches(&client)) {
+fn get_current_matching_window(source: &dyn ClientSource, expr: &MatchExpr) -> Result<Client> {
+    let client = source.current_window()?;
+    if expr.matches(&client) {
         Ok(client)
     } else {
         bail!("Current window does not match provided conditions")
     }
 }
 
-fn main() -> Result<()> {
-    // Get arguments
-    let args: Args = argh::from_env();
+/// Runs the raise-or-launch (or `--rules`) behavior for one request against
+/// a [`ClientSource`]. Shared between the normal one-shot CLI invocation
+/// (backed by [`HyprctlSource`]) and the `--daemon` command socket, which
+/// serves the same requests against a live event-fed cache (see `daemon`).
+pub(crate) fn run(args: &Args, source: &dyn ClientSource) -> Result<()> {
+    let clients = source.clients().ok();
 
-    let matchers = args.build_matchers()?;
+    if let Some(rules_path) = &args.rules {
+        let rules = load_rules(rules_path)?;
+        apply_rules(
+            &clients.unwrap_or_default(),
+            &rules,
+            args.all,
+            |address, consequence| dispatch_consequence(address, consequence).map(|_| ()),
+        )?;
+        return Ok(());
+    }
 
-    // Launch hyprctl
-    let json = Command::new("hyprctl").arg("clients").arg("-j").output();
-    match json {
-        Ok(output) if output.status.success() => {
-            // Deserialize output
-            let stdout = String::from_utf8(output.stdout)
-                .context("Reading `hyprctl clients -j` to string failed")?;
-            let clients = json::from_str::<Vec<Client>>(&stdout)
-                .context("Failed to parse `hyprctl clients -j`")?;
+    let expr = args.build_expr()?;
 
+    match clients {
+        Some(clients) => {
             // Filter matching clients
             let candidates = clients
                 .iter()
-                .filter(|client| matchers.iter().all(|matcher| matcher.matches(*client)))
+                .filter(|client| expr.matches(client))
                 .collect::<Vec<_>>();
 
             // Are we currently focusing a window of this class?
-            if let Ok(current_client) = get_current_matching_window(&matchers) {
+            if let Ok(current_client) = get_current_matching_window(source, &expr) {
                 // Focus next window based on first
                 if let Some(index) = candidates
                     .iter()
                     .position(|client| client.address == current_client.address)
                 {
-                    if let Some(next_client) = candidates.iter().cycle().skip(index + 1).next() {
+                    if let Some(next_client) = candidates.iter().cycle().nth(index + 1) {
                         focus_window(&next_client.address)?;
                     }
                 }
@@ -239,20 +735,36 @@ fn main() -> Result<()> {
                 // Focus first window, otherwise launch command
                 match candidates.first() {
                     Some(Client { address, .. }) => focus_window(address)?,
-                    _ => launch_command(&args)?,
+                    _ => launch_command(args)?,
                 };
             }
         }
-        // If hyprctl fails, just launch it
-        _ => {
-            launch_command(&args)?;
+        None => {
+            launch_command(args)?;
         }
     }
 
-    // Success
     Ok(())
 }
 
+fn main() -> Result<()> {
+    // Get arguments
+    let args: Args = argh::from_env();
+
+    if args.daemon {
+        return daemon::run();
+    }
+
+    // If a daemon is already tracking windows, hand the request to it
+    // instead of shelling out to `hyprctl` ourselves.
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    if daemon::dispatch_to_daemon(&argv)? {
+        return Ok(());
+    }
+
+    run(&args, &HyprctlSource)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,4 +875,279 @@ mod tests {
         let failing = MatchCondition::new(MatchField::XdgTag, Matcher::Equals("video".to_string()));
         assert!(!matches(&failing, &client));
     }
+
+    #[test]
+    fn expr_any_matches_when_one_branch_matches() {
+        let client = build_client("Chromium", None, None, None, None, None);
+        let expr = parse_match_expr("any(class=Firefox, class=Chromium)").unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_all_requires_every_branch() {
+        let client = build_client("Firefox", None, Some("Docs"), None, Some("play"), None);
+        let expr = parse_match_expr("all(class=Firefox, tag=work)").unwrap();
+        assert!(!expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_not_negates_inner_expression() {
+        let client = build_client("Firefox", None, None, None, Some("work"), None);
+        let expr = parse_match_expr("not(tag=play)").unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_nested_groups_respect_depth_when_splitting_commas() {
+        let client = build_client("Firefox", None, None, None, Some("work"), None);
+        let expr = parse_match_expr("all(class=Firefox, any(tag=work, tag=play))").unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_empty_all_is_vacuously_true() {
+        let client = build_client("Firefox", None, None, None, None, None);
+        let expr = parse_match_expr("all()").unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_empty_any_is_vacuously_false() {
+        let client = build_client("Firefox", None, None, None, None, None);
+        let expr = parse_match_expr("any()").unwrap();
+        assert!(!expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_not_rejects_multiple_children() {
+        assert!(parse_match_expr("not(class=Firefox, class=Chromium)").is_err());
+    }
+
+    #[test]
+    fn expr_plain_leaf_parses_like_match_condition() {
+        let client = build_client("Firefox", None, None, None, None, None);
+        let expr = parse_match_expr("class=Firefox").unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_bare_leaf_keeps_commas_in_its_pattern() {
+        let client = build_client(
+            "Gmail",
+            None,
+            Some("Inbox, 5 unread - Gmail"),
+            None,
+            None,
+            None,
+        );
+        let expr = parse_match_expr("title=Inbox, 5 unread - Gmail").unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_grouped_leaf_still_splits_on_top_level_comma() {
+        let client = build_client("Firefox", None, None, None, None, None);
+        let expr = parse_match_expr("any(class=Firefox, class=Chromium)").unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_quoted_leaf_keeps_parens_and_commas_in_a_group() {
+        let client = build_client("Foo", None, Some("Step 3) Done"), None, None, None);
+        let expr = parse_match_expr(r#"any(title="Step 3) Done", class=Bar)"#).unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn expr_quoted_leaf_supports_escaped_quotes_and_backslashes() {
+        let client = build_client("Foo", None, Some(r#"say "hi", \ friend"#), None, None, None);
+        let expr = parse_match_expr(r#"title="say \"hi\", \\ friend""#).unwrap();
+        assert!(expr.matches(&client));
+    }
+
+    #[test]
+    fn consequence_movetoworkspace_dispatch_args() {
+        let consequence = parse_consequence("movetoworkspace:3").unwrap();
+        assert_eq!(
+            consequence.to_dispatch_args("0x123"),
+            vec!["movetoworkspace", "3,address:0x123"]
+        );
+    }
+
+    #[test]
+    fn consequence_resizewindowpixel_requires_width_and_height() {
+        assert!(parse_consequence("resizewindowpixel:640").is_err());
+
+        let consequence = parse_consequence("resizewindowpixel:640,480").unwrap();
+        assert_eq!(
+            consequence.to_dispatch_args("0x123"),
+            vec!["resizewindowpixel", "exact 640 480,address:0x123"]
+        );
+    }
+
+    #[test]
+    fn consequence_exec_ignores_window_address() {
+        let consequence = parse_consequence("exec:foot").unwrap();
+        assert_eq!(consequence.to_dispatch_args("0x123"), vec!["exec", "foot"]);
+    }
+
+    #[test]
+    fn consequence_pin_and_fullscreen_take_no_window_selector() {
+        let pin = parse_consequence("pin").unwrap();
+        assert_eq!(pin.to_dispatch_args("0x123"), vec!["pin"]);
+
+        let fullscreen = parse_consequence("fullscreen").unwrap();
+        assert_eq!(fullscreen.to_dispatch_args("0x123"), vec!["fullscreen"]);
+    }
+
+    #[test]
+    fn consequence_rejects_unknown_verb() {
+        assert!(parse_consequence("levitate").is_err());
+    }
+
+    #[test]
+    fn load_rules_parses_match_and_consequences() {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "raise-test-rules-{:?}.json",
+            std::thread::current().id()
+        ));
+        std::fs::write(
+            &path,
+            r#"{"rules": [{"match": "class=Slack", "consequences": ["movetoworkspace:3", "pin"]}]}"#,
+        )
+        .unwrap();
+
+        let rules = load_rules(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].consequences.len(), 2);
+        let client = build_client("Slack", None, None, None, None, None);
+        assert!(rules[0].expr.matches(&client));
+    }
+
+    fn build_client_with_address(class: &str, address: &str) -> Client {
+        Client {
+            class: class.to_owned(),
+            address: address.to_owned(),
+            initial_class: None,
+            title: None,
+            initial_title: None,
+            tag: None,
+            xdg_tag: None,
+        }
+    }
+
+    #[test]
+    fn apply_rules_default_dispatches_to_first_match_only() {
+        let clients = vec![
+            build_client_with_address("Slack", "0x1"),
+            build_client_with_address("Slack", "0x2"),
+        ];
+        let rules = vec![Rule {
+            expr: parse_match_expr("class=Slack").unwrap(),
+            consequences: vec![Consequence::Pin],
+        }];
+
+        let dispatched = std::cell::RefCell::new(Vec::new());
+        apply_rules(&clients, &rules, false, |address, _consequence| {
+            dispatched.borrow_mut().push(address.to_owned());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(dispatched.into_inner(), vec!["0x1"]);
+    }
+
+    #[test]
+    fn apply_rules_all_dispatches_to_every_match() {
+        let clients = vec![
+            build_client_with_address("Slack", "0x1"),
+            build_client_with_address("Slack", "0x2"),
+            build_client_with_address("Firefox", "0x3"),
+        ];
+        let rules = vec![Rule {
+            expr: parse_match_expr("class=Slack").unwrap(),
+            consequences: vec![Consequence::Pin],
+        }];
+
+        let dispatched = std::cell::RefCell::new(Vec::new());
+        apply_rules(&clients, &rules, true, |address, _consequence| {
+            dispatched.borrow_mut().push(address.to_owned());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(dispatched.into_inner(), vec!["0x1", "0x2"]);
+    }
+
+    #[test]
+    fn negated_contains_excludes_matching_title() {
+        let client = build_client("Firefox", None, Some("Private Browsing"), None, None, None);
+        let condition = parse_match_condition("title:contains!=Private").unwrap();
+        assert!(!matches(&condition, &client));
+
+        let other = build_client("Firefox", None, Some("Docs"), None, None, None);
+        assert!(matches(&condition, &other));
+    }
+
+    #[test]
+    fn negated_equals_without_explicit_method() {
+        let client = build_client("firefox", None, None, None, None, None);
+        let condition = parse_match_condition("class!=chromium").unwrap();
+        assert!(matches(&condition, &client));
+
+        let failing = parse_match_condition("class!=firefox").unwrap();
+        assert!(!matches(&failing, &client));
+    }
+
+    #[test]
+    fn negated_condition_is_vacuously_true_for_an_absent_field() {
+        let client = build_client("Firefox", None, None, None, None, None);
+        let condition = parse_match_condition("title:contains!=Private").unwrap();
+        assert!(matches(&condition, &client));
+
+        let tag_condition = parse_match_condition("tag:contains!=work").unwrap();
+        assert!(matches(&tag_condition, &client));
+    }
+
+    #[test]
+    fn case_insensitive_equals_folds_case() {
+        let client = build_client("Firefox", None, None, None, None, None);
+        let condition = parse_match_condition("class:ieq=firefox").unwrap();
+        assert!(matches(&condition, &client));
+
+        let failing = parse_match_condition("class:ieq=chromium").unwrap();
+        assert!(!matches(&failing, &client));
+    }
+
+    #[test]
+    fn case_insensitive_contains_is_unicode_aware() {
+        let client = build_client("Firefox", None, Some("ÜBER"), None, None, None);
+        let condition = parse_match_condition("title:icontains=über").unwrap();
+        assert!(matches(&condition, &client));
+    }
+
+    #[test]
+    fn glob_matches_wildcard_in_the_middle() {
+        let client = build_client(
+            "Firefox",
+            None,
+            Some("Rickroll - YouTube"),
+            None,
+            None,
+            None,
+        );
+        let condition = parse_match_condition("title:glob=*- YouTube*").unwrap();
+        assert!(matches(&condition, &client));
+
+        let failing = build_client("Firefox", None, Some("Docs"), None, None, None);
+        assert!(!matches(&condition, &failing));
+    }
+
+    #[test]
+    fn glob_rejects_invalid_pattern() {
+        assert!(parse_match_condition("class:glob=[unterminated=firefox").is_err());
+    }
 }